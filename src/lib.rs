@@ -1,15 +1,123 @@
-use std::mem::replace;
+use std::iter::Enumerate;
+use std::mem::{forget, replace, MaybeUninit};
+use std::num::NonZeroUsize;
 use std::ops::{Index, IndexMut};
+use std::ptr;
+use std::vec;
+
+// Number of occupancy bits packed into a single bitmap word.
+const BITS: usize = usize::BITS as usize;
+
+// An index that is never `usize::MAX`, stored as `index + 1` in a
+// `NonZeroUsize`. `Option<NonMaxUsize>` then occupies a single `usize`
+// with no separate discriminant, unlike `Option<usize>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct NonMaxUsize(NonZeroUsize);
+
+impl NonMaxUsize {
+    fn new(index: usize) -> Self {
+        debug_assert!(index != usize::MAX, "index must fit in NonMaxUsize");
+        NonMaxUsize(NonZeroUsize::new(index + 1).expect("index + 1 is never zero"))
+    }
+
+    fn get(self) -> usize {
+        self.0.get() - 1
+    }
+}
+
+/// Key returned by `push`, pairing a slot index with the generation of the
+/// slot at the time the value was inserted.
+///
+/// A `Key` is invalidated as soon as the slot it points at is `pop`ped, even
+/// if a later `push` reuses that slot for a new value. This prevents a
+/// stale `Key` from accidentally aliasing a value it was never issued for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    index: usize,
+    generation: u32,
+}
+
+impl Key {
+    /// Returns the raw slot index this key refers to, ignoring generation.
+    /// Can be used with `get_at`/`pop_at` to bypass the generation check.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// An occupied slot's value together with its links in the order list.
+///
+/// `value` is stored as `MaybeUninit<T>` so that `reserve_with` can hand the
+/// caller in-place access to freshly allocated, not-yet-initialized storage
+/// for the duration of a single call; outside of that call every slot this
+/// container considers `Occupied` holds a fully initialized `T`.
+struct OccupiedNode<T> {
+    value: MaybeUninit<T>,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl<T> OccupiedNode<T> {
+    fn new(value: T) -> Self {
+        OccupiedNode {
+            value: MaybeUninit::new(value),
+            prev: None,
+            next: None,
+        }
+    }
+
+    fn value(&self) -> &T {
+        unsafe { &*self.value.as_ptr() }
+    }
+
+    fn value_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.value.as_mut_ptr() }
+    }
+
+    fn into_value(self) -> T {
+        // Read the value out, then `forget` `self` so our `Drop` impl does
+        // not also run the destructor for the bytes we just moved out.
+        let value = unsafe { self.value.as_ptr().read() };
+        forget(self);
+        value
+    }
+}
+
+impl<T> Drop for OccupiedNode<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.value.as_mut_ptr()) }
+    }
+}
+
+impl<T: Clone> Clone for OccupiedNode<T> {
+    fn clone(&self) -> Self {
+        OccupiedNode {
+            value: MaybeUninit::new(self.value().clone()),
+            prev: self.prev,
+            next: self.next,
+        }
+    }
+}
+
+impl<T: ::std::fmt::Debug> ::std::fmt::Debug for OccupiedNode<T> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.debug_struct("OccupiedNode")
+            .field("value", self.value())
+            .field("prev", &self.prev)
+            .field("next", &self.next)
+            .finish()
+    }
+}
 
 /// Node of the list
 #[derive(Clone, Debug)]
 enum Node<T> {
-    Vacant(Option<usize>),
-    Occupied(T),
+    Vacant(Option<NonMaxUsize>),
+    Occupied(OccupiedNode<T>),
 }
 
 impl<T> Node<T> {
-    fn expect_vacant(&self) -> Option<usize> {
+    fn expect_vacant(&self) -> Option<NonMaxUsize> {
         match *self {
             Node::Vacant(next) => next,
             Node::Occupied(_) => panic!("Node is occupied"),
@@ -19,11 +127,11 @@ impl<T> Node<T> {
     fn expect_occupied(self) -> T {
         match self {
             Node::Vacant(_) => panic!("Node is vacant"),
-            Node::Occupied(value) => value,
+            Node::Occupied(node) => node.into_value(),
         }
     }
 
-    fn free(&mut self, next: Option<usize>) -> Option<T> {
+    fn free(&mut self, next: Option<NonMaxUsize>) -> Option<T> {
         match *self {
             Node::Vacant(_) => return None,
             _ => {}
@@ -34,12 +142,43 @@ impl<T> Node<T> {
 
 /// `Vec` with slots which allow to `pop` values from index
 /// which will be reused by later `push`.
+///
+/// Occupied slots are additionally threaded into a doubly-linked order list
+/// (`head`/`tail`), independent of their storage index, so that values have
+/// a well-defined sequence that can be reshuffled with `push_front`,
+/// `insert_before`/`insert_after` and `move_to_front`/`move_to_back`
+/// without touching their index.
 #[derive(Clone, Debug)]
 pub struct VecList<T> {
     // next free slot
-    free: Option<usize>,
+    free: Option<NonMaxUsize>,
     // slots
     data: Vec<Node<T>>,
+    // generation of each slot, bumped every time it is freed
+    generations: Vec<u32>,
+    // one bit per slot, set while the slot is occupied
+    occupied: Vec<usize>,
+    // number of occupied slots
+    len: usize,
+    // first slot in order-list traversal order
+    head: Option<usize>,
+    // last slot in order-list traversal order
+    tail: Option<usize>,
+}
+
+fn bit_set(bits: &mut Vec<usize>, index: usize) {
+    let word = index / BITS;
+    let bit = index % BITS;
+    if word >= bits.len() {
+        bits.resize(word + 1, 0);
+    }
+    bits[word] |= 1 << bit;
+}
+
+fn bit_clear(bits: &mut [usize], index: usize) {
+    let word = index / BITS;
+    let bit = index % BITS;
+    bits[word] &= !(1 << bit);
 }
 
 impl<T> Default for VecList<T> {
@@ -54,6 +193,11 @@ impl<T> VecList<T> {
         VecList {
             free: None,
             data: Vec::new(),
+            generations: Vec::new(),
+            occupied: Vec::new(),
+            len: 0,
+            head: None,
+            tail: None,
         }
     }
 
@@ -62,49 +206,369 @@ impl<T> VecList<T> {
         VecList {
             free: None,
             data: Vec::with_capacity(cap),
+            generations: Vec::with_capacity(cap),
+            occupied: Vec::with_capacity(cap.div_ceil(BITS)),
+            len: 0,
+            head: None,
+            tail: None,
+        }
+    }
+
+    /// Push new value into `VecList` returning a `Key` that can later be
+    /// used to `get`/`get_mut`/`pop` this exact value.
+    ///
+    /// Equivalent to `push_back`.
+    pub fn push(&mut self, value: T) -> Key {
+        self.push_back(value)
+    }
+
+    /// Push new value into `VecList` returning the raw index
+    /// where value is placed, with no generation protection.
+    ///
+    /// Equivalent to `push_back_at`.
+    pub fn push_at(&mut self, value: T) -> usize {
+        self.push_back_at(value)
+    }
+
+    /// Push a value onto the back of the order list, returning a `Key`.
+    pub fn push_back(&mut self, value: T) -> Key {
+        let index = self.push_back_at(value);
+        Key {
+            index,
+            generation: self.generations[index],
+        }
+    }
+
+    /// Push a value onto the front of the order list, returning a `Key`.
+    pub fn push_front(&mut self, value: T) -> Key {
+        let index = self.push_front_at(value);
+        Key {
+            index,
+            generation: self.generations[index],
+        }
+    }
+
+    /// Push a value onto the back of the order list, returning the raw index.
+    pub fn push_back_at(&mut self, value: T) -> usize {
+        let index = self.alloc_slot(value);
+        self.link_back(index);
+        index
+    }
+
+    /// Push a value onto the front of the order list, returning the raw index.
+    pub fn push_front_at(&mut self, value: T) -> usize {
+        let index = self.alloc_slot(value);
+        self.link_front(index);
+        index
+    }
+
+    /// Insert `value` immediately before the slot referenced by `key` in the
+    /// order list, returning a `Key` for the new slot, or `None` if `key` is
+    /// stale or unused.
+    pub fn insert_before(&mut self, key: Key, value: T) -> Option<Key> {
+        if self.generations.get(key.index).copied() != Some(key.generation) {
+            return None;
+        }
+        let index = self.insert_before_at(key.index, value);
+        Some(Key {
+            index,
+            generation: self.generations[index],
+        })
+    }
+
+    /// Insert `value` immediately after the slot referenced by `key` in the
+    /// order list, returning a `Key` for the new slot, or `None` if `key` is
+    /// stale or unused.
+    pub fn insert_after(&mut self, key: Key, value: T) -> Option<Key> {
+        if self.generations.get(key.index).copied() != Some(key.generation) {
+            return None;
+        }
+        let index = self.insert_after_at(key.index, value);
+        Some(Key {
+            index,
+            generation: self.generations[index],
+        })
+    }
+
+    /// Insert `value` immediately before `index` in the order list, returning
+    /// the raw index of the new slot. Panics if `index` is not occupied.
+    pub fn insert_before_at(&mut self, index: usize, value: T) -> usize {
+        let prev = self.prev_of(index);
+        let new_index = self.alloc_slot(value);
+        self.set_prev(new_index, prev);
+        self.set_next(new_index, Some(index));
+        match prev {
+            Some(prev) => self.set_next(prev, Some(new_index)),
+            None => self.head = Some(new_index),
+        }
+        self.set_prev(index, Some(new_index));
+        new_index
+    }
+
+    /// Insert `value` immediately after `index` in the order list, returning
+    /// the raw index of the new slot. Panics if `index` is not occupied.
+    pub fn insert_after_at(&mut self, index: usize, value: T) -> usize {
+        let next = self.next_of(index);
+        let new_index = self.alloc_slot(value);
+        self.set_next(new_index, next);
+        self.set_prev(new_index, Some(index));
+        match next {
+            Some(next) => self.set_prev(next, Some(new_index)),
+            None => self.tail = Some(new_index),
+        }
+        self.set_next(index, Some(new_index));
+        new_index
+    }
+
+    /// Move the slot referenced by `key` to the front of the order list.
+    /// Returns `false` if `key` is stale or unused.
+    pub fn move_to_front(&mut self, key: Key) -> bool {
+        if self.generations.get(key.index).copied() != Some(key.generation) {
+            return false;
+        }
+        self.move_to_front_at(key.index)
+    }
+
+    /// Move the slot referenced by `key` to the back of the order list.
+    /// Returns `false` if `key` is stale or unused.
+    pub fn move_to_back(&mut self, key: Key) -> bool {
+        if self.generations.get(key.index).copied() != Some(key.generation) {
+            return false;
+        }
+        self.move_to_back_at(key.index)
+    }
+
+    /// Move the occupied slot at `index` to the front of the order list.
+    /// Returns `false` if `index` is not occupied.
+    pub fn move_to_front_at(&mut self, index: usize) -> bool {
+        if !self.is_occupied(index) {
+            return false;
+        }
+        self.unlink(index);
+        self.link_front(index);
+        true
+    }
+
+    /// Move the occupied slot at `index` to the back of the order list.
+    /// Returns `false` if `index` is not occupied.
+    pub fn move_to_back_at(&mut self, index: usize) -> bool {
+        if !self.is_occupied(index) {
+            return false;
+        }
+        self.unlink(index);
+        self.link_back(index);
+        true
+    }
+
+    /// Pop value for the given `Key`.
+    /// Returns `None` if the key is stale or unused.
+    pub fn pop(&mut self, key: Key) -> Option<T> {
+        if self.generations.get(key.index).copied() != Some(key.generation) {
+            return None;
+        }
+        self.pop_at(key.index)
+    }
+
+    /// Pop value from specified raw index, ignoring generation.
+    /// Returns `None` if index is unused.
+    pub fn pop_at(&mut self, index: usize) -> Option<T> {
+        if !self.is_occupied(index) {
+            return None;
+        }
+        Some(self.free_slot(index))
+    }
+
+    /// Reserve a new slot on the back of the order list and hand `init` a
+    /// `&mut MaybeUninit<T>` pointing at its freshly allocated, uninitialized
+    /// storage, so that `T` can be constructed directly in place instead of
+    /// being built elsewhere and moved in by `push`. Returns the raw index
+    /// of the new slot.
+    ///
+    /// # Safety
+    ///
+    /// `init` must leave the `MaybeUninit<T>` fully initialized (e.g. via
+    /// `MaybeUninit::write`) before returning. Every other `VecList` method
+    /// assumes an occupied slot holds a valid `T`; an uninitialized slot is
+    /// undefined behavior as soon as it is read, iterated or dropped.
+    pub unsafe fn reserve_with(&mut self, init: impl FnOnce(&mut MaybeUninit<T>)) -> usize {
+        let index = self.alloc_node(OccupiedNode {
+            value: MaybeUninit::uninit(),
+            prev: None,
+            next: None,
+        });
+        match self.data[index] {
+            Node::Occupied(ref mut node) => init(&mut node.value),
+            Node::Vacant(_) => unreachable!("slot was just allocated"),
+        }
+        self.link_back(index);
+        index
+    }
+
+    /// Call `f` with a mutable reference to the value at `index`, then free
+    /// the slot. Unlike `pop_at`, the value is never moved out to the
+    /// caller, so `f` can drain or swap its contents in place (e.g. take an
+    /// owned buffer out with `mem::take`, leaving a cheap placeholder to
+    /// drop) instead of paying for a full value to be handed back.
+    /// Returns `false` if `index` is not occupied.
+    pub fn remove_with(&mut self, index: usize, f: impl FnOnce(&mut T)) -> bool {
+        if !self.is_occupied(index) {
+            return false;
+        }
+        if let Node::Occupied(ref mut node) = self.data[index] {
+            f(node.value_mut());
         }
+        self.free_slot(index);
+        true
+    }
+
+    fn is_occupied(&self, index: usize) -> bool {
+        matches!(self.data.get(index), Some(Node::Occupied(_)))
+    }
+
+    // Unlinks and frees an occupied slot, returning its value. Shared by
+    // `pop_at` (which hands the value back) and `remove_with` (which lets
+    // the value drop after its contents have been drained in place).
+    fn free_slot(&mut self, index: usize) -> T {
+        self.unlink(index);
+        let value = self.data[index]
+            .free(self.free)
+            .expect("index was checked to be occupied");
+        self.free = Some(NonMaxUsize::new(index));
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        bit_clear(&mut self.occupied, index);
+        self.len -= 1;
+        value
     }
 
-    /// Push new value into `VecList` returning index
-    /// where value is placed.
-    pub fn push(&mut self, value: T) -> usize {
-        if let Some(free) = self.free {
+    fn alloc_slot(&mut self, value: T) -> usize {
+        self.alloc_node(OccupiedNode::new(value))
+    }
+
+    fn alloc_node(&mut self, node: OccupiedNode<T>) -> usize {
+        let index = if let Some(free) = self.free {
+            let free = free.get();
             debug_assert!(free < self.data.len());
-            let old = replace(&mut self.data[free], Node::Occupied(value));
-            replace(&mut self.free, old.expect_vacant()).unwrap()
+            let old = replace(&mut self.data[free], Node::Occupied(node));
+            self.free = old.expect_vacant();
+            free
         } else {
             // No free nodes available
-            self.data.push(Node::Occupied(value));
-            self.data.len() - 1
+            self.data.push(Node::Occupied(node));
+            let index = self.data.len() - 1;
+            // A slot dropped by `shrink_to_fit` keeps its generation, so it
+            // only starts at 0 the first time it is created.
+            if index == self.generations.len() {
+                self.generations.push(0);
+            }
+            index
+        };
+        bit_set(&mut self.occupied, index);
+        self.len += 1;
+        index
+    }
+
+    fn prev_of(&self, index: usize) -> Option<usize> {
+        match self.data[index] {
+            Node::Occupied(ref node) => node.prev,
+            Node::Vacant(_) => panic!("index is vacant"),
         }
     }
 
-    /// Pop value from specified index.
-    /// Returns `None` if index is unused.
-    pub fn pop(&mut self, index: usize) -> Option<T> {
-        if index > self.data.len() {
-            None
-        } else {
-            self.data[index].free(self.free).map(|value| {
-                self.free = Some(index);
-                value
-            })
+    fn next_of(&self, index: usize) -> Option<usize> {
+        match self.data[index] {
+            Node::Occupied(ref node) => node.next,
+            Node::Vacant(_) => panic!("index is vacant"),
+        }
+    }
+
+    fn set_prev(&mut self, index: usize, prev: Option<usize>) {
+        match self.data[index] {
+            Node::Occupied(ref mut node) => node.prev = prev,
+            Node::Vacant(_) => unreachable!("neighbor must be occupied"),
+        }
+    }
+
+    fn set_next(&mut self, index: usize, next: Option<usize>) {
+        match self.data[index] {
+            Node::Occupied(ref mut node) => node.next = next,
+            Node::Vacant(_) => unreachable!("neighbor must be occupied"),
+        }
+    }
+
+    // Links a freshly allocated, unlinked slot onto the back of the order list.
+    fn link_back(&mut self, index: usize) {
+        let prev = self.tail;
+        self.set_prev(index, prev);
+        self.set_next(index, None);
+        match prev {
+            Some(prev) => self.set_next(prev, Some(index)),
+            None => self.head = Some(index),
         }
+        self.tail = Some(index);
     }
 
-    /// Returns a reference to the value of given index or `None` if there is no value yet.
-    pub fn get(&self, index: usize) -> Option<&T> {
+    // Links a freshly allocated, unlinked slot onto the front of the order list.
+    fn link_front(&mut self, index: usize) {
+        let next = self.head;
+        self.set_next(index, next);
+        self.set_prev(index, None);
+        match next {
+            Some(next) => self.set_prev(next, Some(index)),
+            None => self.tail = Some(index),
+        }
+        self.head = Some(index);
+    }
+
+    // Unlinks an occupied slot from the order list, patching its neighbors.
+    // O(1), no search.
+    fn unlink(&mut self, index: usize) {
+        let (prev, next) = match self.data[index] {
+            Node::Occupied(ref node) => (node.prev, node.next),
+            Node::Vacant(_) => panic!("index is vacant"),
+        };
+        match prev {
+            Some(prev) => self.set_next(prev, next),
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.set_prev(next, prev),
+            None => self.tail = prev,
+        }
+    }
+
+    /// Returns a reference to the value for the given `Key`, or `None` if
+    /// the key is stale or unused.
+    pub fn get(&self, key: Key) -> Option<&T> {
+        if self.generations.get(key.index).copied() != Some(key.generation) {
+            return None;
+        }
+        self.get_at(key.index)
+    }
+
+    /// Returns a mutable reference to the value for the given `Key`, or
+    /// `None` if the key is stale or unused.
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        if self.generations.get(key.index).copied() != Some(key.generation) {
+            return None;
+        }
+        self.get_at_mut(key.index)
+    }
+
+    /// Returns a reference to the value at the raw index, ignoring
+    /// generation, or `None` if there is no value there.
+    pub fn get_at(&self, index: usize) -> Option<&T> {
         self.data.get(index).and_then(|node| match *node {
             Node::Vacant(_) => None,
-            Node::Occupied(ref value) => Some(value),
+            Node::Occupied(ref node) => Some(node.value()),
         })
     }
 
-    /// Returns a mutable reference to the value of given index or `None` if there is no value yet.
-    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+    /// Returns a mutable reference to the value at the raw index, ignoring
+    /// generation, or `None` if there is no value there.
+    pub fn get_at_mut(&mut self, index: usize) -> Option<&mut T> {
         self.data.get_mut(index).and_then(|node| match *node {
             Node::Vacant(_) => None,
-            Node::Occupied(ref mut value) => Some(value),
+            Node::Occupied(ref mut node) => Some(node.value_mut()),
         })
     }
 
@@ -112,18 +576,362 @@ impl<T> VecList<T> {
     pub fn upper_bound(&self) -> usize {
         self.data.len()
     }
+
+    /// Returns the number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no occupied slots.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of slots the backing storage can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Removes all values, dropping them and invalidating every outstanding
+    /// `Key`, but keeps the backing storage's capacity for reuse.
+    pub fn clear(&mut self) {
+        for index in 0..self.data.len() {
+            if let Node::Occupied(_) = self.data[index] {
+                self.free_slot(index);
+            }
+        }
+    }
+
+    /// Drops trailing vacant slots, shrinking the backing storage to the
+    /// smallest size that still holds every occupied slot, and shrinks the
+    /// allocations to fit. Occupied slots keep their index, so existing
+    /// `Key`s remain valid. The generations of dropped slots are kept, so a
+    /// stale `Key` for a dropped slot stays invalid when a later `push`
+    /// recreates that slot.
+    pub fn shrink_to_fit(&mut self) {
+        while let Some(&Node::Vacant(_)) = self.data.last() {
+            self.data.pop();
+        }
+        let len = self.data.len();
+        self.occupied.truncate(len.div_ceil(BITS));
+
+        self.free = None;
+        for index in (0..len).rev() {
+            if let Node::Vacant(_) = self.data[index] {
+                self.data[index] = Node::Vacant(self.free);
+                self.free = Some(NonMaxUsize::new(index));
+            }
+        }
+
+        self.data.shrink_to_fit();
+        self.generations.shrink_to_fit();
+        self.occupied.shrink_to_fit();
+    }
+
+    /// Moves every occupied value down into the lowest available slots,
+    /// eliminating the gaps left by `pop`, then calls `shrink_to_fit` to
+    /// release the reclaimed tail capacity. `relocate(old_index, new_index)`
+    /// is called for every value that moved, so callers can fix up any raw
+    /// indices they have stored externally. A moved value takes the
+    /// generation of the slot it moves into, so its old `Key` no longer
+    /// resolves and the value is reached through `get_at` with its new
+    /// index; `Key`s of values that did not move stay valid.
+    pub fn compact(&mut self, mut relocate: impl FnMut(usize, usize)) {
+        let len = self.data.len();
+        let mut new_index = vec![0usize; len];
+        let mut write = 0;
+        for (read, node) in self.data.iter().enumerate() {
+            if let Node::Occupied(_) = *node {
+                new_index[read] = write;
+                write += 1;
+            }
+        }
+
+        for node in &mut self.data {
+            if let Node::Occupied(ref mut node) = *node {
+                node.prev = node.prev.map(|index| new_index[index]);
+                node.next = node.next.map(|index| new_index[index]);
+            }
+        }
+        self.head = self.head.map(|index| new_index[index]);
+        self.tail = self.tail.map(|index| new_index[index]);
+
+        let mut write = 0;
+        for read in 0..len {
+            if let Node::Occupied(_) = self.data[read] {
+                if write != read {
+                    self.data.swap(write, read);
+                    // The vacated slot counts as freed.
+                    self.generations[read] = self.generations[read].wrapping_add(1);
+                    bit_clear(&mut self.occupied, read);
+                    bit_set(&mut self.occupied, write);
+                    relocate(read, write);
+                }
+                write += 1;
+            }
+        }
+
+        self.shrink_to_fit();
+    }
+
+    /// Iterate over `(index, &T)` pairs for occupied slots, in index order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            bits: Bits::new(&self.occupied),
+            data: &self.data,
+        }
+    }
+
+    /// Iterate over `(index, &mut T)` pairs for occupied slots, in index order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            bits: Bits::new(&self.occupied),
+            data: &mut self.data,
+        }
+    }
+
+    /// Iterate over the indices of occupied slots, in index order.
+    pub fn keys(&self) -> Keys<'_, T> {
+        Keys { iter: self.iter() }
+    }
+
+    /// Iterate over references to occupied values, in index order.
+    pub fn values(&self) -> Values<'_, T> {
+        Values { iter: self.iter() }
+    }
+
+    /// Iterate over `(index, &T)` pairs in order-list order, i.e. the order
+    /// established by `push_back`/`push_front`/`insert_before`/
+    /// `insert_after`/`move_to_front`/`move_to_back`, rather than index order.
+    pub fn iter_ordered(&self) -> IterOrdered<'_, T> {
+        IterOrdered {
+            data: &self.data,
+            next: self.head,
+        }
+    }
+
+    /// Returns a cursor positioned at the front of the order list.
+    pub fn cursor_front(&self) -> Cursor {
+        Cursor { index: self.head }
+    }
+
+    /// Returns a cursor positioned at the back of the order list.
+    pub fn cursor_back(&self) -> Cursor {
+        Cursor { index: self.tail }
+    }
+}
+
+/// A position within a `VecList`'s order list, independent of any
+/// particular borrow of the list. Obtained via `VecList::cursor_front`/
+/// `VecList::cursor_back`, and moved with `move_next`/`move_prev`.
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor {
+    index: Option<usize>,
+}
+
+impl Cursor {
+    /// Returns the raw index the cursor currently points at, or `None` if
+    /// it has moved past either end of the order list.
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// Returns a reference to the value at the cursor's position.
+    pub fn get<'a, T>(&self, list: &'a VecList<T>) -> Option<&'a T> {
+        self.index.and_then(|index| list.get_at(index))
+    }
+
+    /// Moves the cursor to the next slot in order-list order. If the slot
+    /// the cursor currently points at has since been popped, the cursor is
+    /// left past the end (`index()` returns `None`) instead of panicking.
+    pub fn move_next<T>(&mut self, list: &VecList<T>) {
+        self.index = match self.index {
+            Some(index) if list.is_occupied(index) => list.next_of(index),
+            _ => None,
+        };
+    }
+
+    /// Moves the cursor to the previous slot in order-list order. If the
+    /// slot the cursor currently points at has since been popped, the
+    /// cursor is left past the start (`index()` returns `None`) instead of
+    /// panicking.
+    pub fn move_prev<T>(&mut self, list: &VecList<T>) {
+        self.index = match self.index {
+            Some(index) if list.is_occupied(index) => list.prev_of(index),
+            _ => None,
+        };
+    }
 }
 
-impl<T> Index<usize> for VecList<T> {
+/// Iterator over `(index, &T)` pairs in order-list order. See `VecList::iter_ordered`.
+pub struct IterOrdered<'a, T: 'a> {
+    data: &'a [Node<T>],
+    next: Option<usize>,
+}
+
+impl<'a, T> Iterator for IterOrdered<'a, T> {
+    type Item = (usize, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.next?;
+        match self.data[index] {
+            Node::Occupied(ref node) => {
+                self.next = node.next;
+                Some((index, node.value()))
+            }
+            Node::Vacant(_) => unreachable!("order list points at vacant slot"),
+        }
+    }
+}
+
+// Iterates the set bits of an occupancy bitmap, skipping zero words with a
+// single compare and using `trailing_zeros` to find bits within a word.
+struct Bits<'a> {
+    words: &'a [usize],
+    word_idx: usize,
+    word: usize,
+}
+
+impl<'a> Bits<'a> {
+    fn new(words: &'a [usize]) -> Self {
+        Bits {
+            words,
+            word_idx: usize::MAX,
+            word: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Bits<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.word_idx = self.word_idx.wrapping_add(1);
+            if self.word_idx >= self.words.len() {
+                return None;
+            }
+            self.word = self.words[self.word_idx];
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(self.word_idx * BITS + bit)
+    }
+}
+
+/// Iterator over `(index, &T)` pairs of occupied slots. See `VecList::iter`.
+pub struct Iter<'a, T: 'a> {
+    bits: Bits<'a>,
+    data: &'a [Node<T>],
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (usize, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.bits.next().map(|index| match self.data[index] {
+            Node::Occupied(ref node) => (index, node.value()),
+            Node::Vacant(_) => unreachable!("occupancy bitmap out of sync"),
+        })
+    }
+}
+
+/// Iterator over `(index, &mut T)` pairs of occupied slots. See `VecList::iter_mut`.
+pub struct IterMut<'a, T: 'a> {
+    bits: Bits<'a>,
+    data: &'a mut [Node<T>],
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.bits.next().map(|index| {
+            // Each index yielded by `Bits` is distinct, so handing out
+            // non-overlapping `&mut` borrows for them is sound.
+            let node = unsafe { &mut *(self.data.get_unchecked_mut(index) as *mut Node<T>) };
+            match *node {
+                Node::Occupied(ref mut node) => (index, node.value_mut()),
+                Node::Vacant(_) => unreachable!("occupancy bitmap out of sync"),
+            }
+        })
+    }
+}
+
+/// Iterator over indices of occupied slots. See `VecList::keys`.
+pub struct Keys<'a, T: 'a> {
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Keys<'a, T> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        self.iter.next().map(|(index, _)| index)
+    }
+}
+
+/// Iterator over references to occupied values. See `VecList::values`.
+pub struct Values<'a, T: 'a> {
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Values<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next().map(|(_, value)| value)
+    }
+}
+
+/// Owning iterator over occupied values, in index order. See `IntoIterator`.
+pub struct IntoIter<T> {
+    data: Enumerate<vec::IntoIter<Node<T>>>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (usize, T);
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, node) in &mut self.data {
+            if let Node::Occupied(node) = node {
+                return Some((index, node.into_value()));
+            }
+        }
+        None
+    }
+}
+
+impl<T> IntoIterator for VecList<T> {
+    type Item = (usize, T);
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            data: self.data.into_iter().enumerate(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VecList<T> {
+    type Item = (usize, &'a T);
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut VecList<T> {
+    type Item = (usize, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Index<Key> for VecList<T> {
     type Output = T;
-    fn index(&self, index: usize) -> &T {
-        self.get(index).expect("Expect occupied")
+    fn index(&self, key: Key) -> &T {
+        self.get(key).expect("Expect occupied")
     }
 }
 
-impl<T> IndexMut<usize> for VecList<T> {
-    fn index_mut(&mut self, index: usize) -> &mut T {
-        self.get_mut(index).expect("Expect occupied")
+impl<T> IndexMut<Key> for VecList<T> {
+    fn index_mut(&mut self, key: Key) -> &mut T {
+        self.get_mut(key).expect("Expect occupied")
     }
 }
 
@@ -135,12 +943,10 @@ mod tests {
     fn test_push() {
         let mut veclist = VecList::new();
 
-        for i in 0..10 {
-            veclist.push(i);
-        }
+        let keys: Vec<_> = (0..10).map(|i| veclist.push(i)).collect();
 
-        for i in 0..10 {
-            assert_eq!(veclist.get(i), Some(&i));
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(veclist.get(*key), Some(&i));
         }
     }
 
@@ -148,42 +954,337 @@ mod tests {
     fn test_pop() {
         let mut veclist = VecList::new();
 
+        let keys: Vec<_> = (0..10).map(|i| veclist.push(i)).collect();
+
+        for (i, key) in keys.iter().enumerate().take(5) {
+            assert_eq!(veclist.pop(*key), Some(i));
+        }
+
+        for key in keys.iter().take(5) {
+            assert_eq!(veclist.get(*key), None);
+        }
+
+        for (i, key) in keys.iter().enumerate().skip(6) {
+            assert_eq!(veclist[*key], i);
+        }
+    }
+
+    #[test]
+    fn test_reuse() {
+        let mut veclist = VecList::new();
+
         for i in 0..10 {
-            veclist.push(i);
+            veclist.push_at(i);
         }
 
         for i in 0..5 {
-            assert_eq!(veclist.pop(i), Some(i));
+            assert_eq!(veclist.pop_at(i), Some(i));
         }
 
         for i in 0..5 {
-            assert_eq!(veclist.get(i), None);
+            veclist.push_at(i + 10);
         }
 
-        for i in 6..10 {
-            assert_eq!(veclist[i], i);
+        for i in 0..5 {
+            // reused in LIFO manner
+            assert_eq!(veclist.get_at(i), Some(&(14 - i)));
         }
     }
 
     #[test]
-    fn test_reuse() {
+    fn test_iter_skips_vacant() {
         let mut veclist = VecList::new();
 
-        for i in 0..10 {
-            veclist.push(i);
+        let keys: Vec<_> = (0..10).map(|i| veclist.push(i)).collect();
+        for key in &keys[0..5] {
+            veclist.pop(*key);
         }
 
+        assert_eq!(veclist.len(), 5);
+        assert!(!veclist.is_empty());
+
+        let collected: Vec<_> = veclist.iter().map(|(index, value)| (index, *value)).collect();
+        assert_eq!(collected, vec![(5, 5), (6, 6), (7, 7), (8, 8), (9, 9)]);
+
+        let values: Vec<_> = veclist.values().cloned().collect();
+        assert_eq!(values, vec![5, 6, 7, 8, 9]);
+
+        let keys: Vec<_> = veclist.keys().collect();
+        assert_eq!(keys, vec![5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut veclist = VecList::new();
         for i in 0..5 {
-            assert_eq!(veclist.pop(i), Some(i));
+            veclist.push(i);
         }
 
+        for (_, value) in veclist.iter_mut() {
+            *value *= 10;
+        }
+
+        let values: Vec<_> = veclist.values().cloned().collect();
+        assert_eq!(values, vec![0, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut veclist = VecList::new();
         for i in 0..5 {
-            veclist.push(i + 10);
+            veclist.push(i);
         }
+        veclist.pop_at(2);
 
+        let collected: Vec<_> = veclist.into_iter().collect();
+        assert_eq!(collected, vec![(0, 0), (1, 1), (3, 3), (4, 4)]);
+    }
+
+    #[test]
+    fn test_iter_ordered_push_front_back() {
+        let mut veclist = VecList::new();
+
+        veclist.push_back_at(1);
+        veclist.push_back_at(2);
+        veclist.push_front_at(0);
+
+        let ordered: Vec<_> = veclist.iter_ordered().map(|(_, v)| *v).collect();
+        assert_eq!(ordered, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_insert_before_after() {
+        let mut veclist = VecList::new();
+
+        let a = veclist.push_back_at('a');
+        let d = veclist.push_back_at('d');
+        veclist.insert_before_at(d, 'c');
+        veclist.insert_after_at(a, 'b');
+
+        let ordered: Vec<_> = veclist.iter_ordered().map(|(_, v)| *v).collect();
+        assert_eq!(ordered, vec!['a', 'b', 'c', 'd']);
+    }
+
+    #[test]
+    fn test_move_to_front_and_back() {
+        let mut veclist = VecList::new();
+
+        let a = veclist.push_back_at('a');
+        veclist.push_back_at('b');
+        let c = veclist.push_back_at('c');
+
+        veclist.move_to_front_at(c);
+        assert_eq!(
+            veclist.iter_ordered().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec!['c', 'a', 'b']
+        );
+
+        veclist.move_to_back_at(a);
+        assert_eq!(
+            veclist.iter_ordered().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec!['c', 'b', 'a']
+        );
+    }
+
+    #[test]
+    fn test_key_based_insert_and_move() {
+        let mut veclist = VecList::new();
+
+        let a = veclist.push_back('a');
+        let d = veclist.push_back('d');
+        assert!(veclist.insert_before(d, 'c').is_some());
+        assert!(veclist.insert_after(a, 'b').is_some());
+
+        assert_eq!(
+            veclist.iter_ordered().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec!['a', 'b', 'c', 'd']
+        );
+
+        veclist.move_to_front(d);
+        assert_eq!(
+            veclist.iter_ordered().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec!['d', 'a', 'b', 'c']
+        );
+
+        veclist.move_to_back(a);
+        assert_eq!(
+            veclist.iter_ordered().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec!['d', 'b', 'c', 'a']
+        );
+    }
+
+    #[test]
+    fn test_pop_unlinks_node() {
+        let mut veclist = VecList::new();
+
+        veclist.push_back_at(1);
+        let two = veclist.push_back_at(2);
+        veclist.push_back_at(3);
+
+        veclist.pop_at(two);
+
+        let ordered: Vec<_> = veclist.iter_ordered().map(|(_, v)| *v).collect();
+        assert_eq!(ordered, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_cursor_walks_order_list() {
+        let mut veclist = VecList::new();
+        veclist.push_back_at('a');
+        veclist.push_back_at('b');
+        veclist.push_back_at('c');
+
+        let mut cursor = veclist.cursor_front();
+        let mut seen = Vec::new();
+        while let Some(value) = cursor.get(&veclist) {
+            seen.push(*value);
+            cursor.move_next(&veclist);
+        }
+        assert_eq!(seen, vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    fn test_cursor_survives_removal_of_current_slot() {
+        let mut veclist = VecList::new();
+        veclist.push_back_at('a');
+        let b = veclist.push_back_at('b');
+        veclist.push_back_at('c');
+
+        let mut cursor = veclist.cursor_front();
+        cursor.move_next(&veclist);
+        assert_eq!(cursor.index(), Some(b));
+
+        veclist.pop_at(b);
+
+        // The cursor still points at the now-vacant slot; walking from here
+        // must not panic, and instead lands past the end of the list.
+        cursor.move_next(&veclist);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.get(&veclist), None);
+    }
+
+    #[test]
+    fn test_reserve_with_and_remove_with() {
+        let mut veclist = VecList::new();
+
+        let index = unsafe {
+            veclist.reserve_with(|slot| {
+                slot.as_mut_ptr().write(vec![1, 2, 3]);
+            })
+        };
+        assert_eq!(veclist.get_at(index), Some(&vec![1, 2, 3]));
+
+        let mut drained = Vec::new();
+        let removed = veclist.remove_with(index, |value| {
+            drained = ::std::mem::take(value);
+        });
+        assert!(removed);
+        assert_eq!(drained, vec![1, 2, 3]);
+        assert_eq!(veclist.get_at(index), None);
+        assert!(!veclist.remove_with(index, |_| {}));
+    }
+
+    #[test]
+    fn test_clear_invalidates_keys_and_keeps_capacity() {
+        let mut veclist = VecList::new();
+        let keys: Vec<_> = (0..5).map(|i| veclist.push(i)).collect();
+        let capacity = veclist.capacity();
+
+        veclist.clear();
+
+        assert_eq!(veclist.len(), 0);
+        assert!(veclist.is_empty());
+        assert_eq!(veclist.capacity(), capacity);
+        for key in keys {
+            assert_eq!(veclist.get(key), None);
+        }
+
+        // Slots are reused after clearing, same as after individual pops.
+        let key = veclist.push(10);
+        assert_eq!(veclist.get(key), Some(&10));
+    }
+
+    #[test]
+    fn test_shrink_to_fit_drops_trailing_vacant_slots() {
+        let mut veclist = VecList::new();
         for i in 0..5 {
-            // reused in LIFO manner
-            assert_eq!(veclist[i], 14 - i);
+            veclist.push_at(i);
         }
+        veclist.pop_at(4);
+        veclist.pop_at(3);
+        veclist.pop_at(1);
+
+        veclist.shrink_to_fit();
+
+        // Slot 1 is vacant but not trailing, so it is kept and reusable;
+        // slots 3 and 4 were trailing vacant and are dropped.
+        assert_eq!(veclist.upper_bound(), 3);
+        assert_eq!(veclist.get_at(0), Some(&0));
+        assert_eq!(veclist.get_at(1), None);
+        assert_eq!(veclist.get_at(2), Some(&2));
+
+        let reused = veclist.push_at(20);
+        assert_eq!(reused, 1);
+        assert_eq!(veclist.get_at(1), Some(&20));
+    }
+
+    #[test]
+    fn test_compact_relocates_and_preserves_order() {
+        let mut veclist = VecList::new();
+        let a = veclist.push_back_at('a');
+        veclist.push_back_at('b');
+        let c = veclist.push_back_at('c');
+        veclist.push_back_at('d');
+        veclist.pop_at(a);
+        veclist.pop_at(c);
+
+        let mut moves = Vec::new();
+        veclist.compact(|from, to| moves.push((from, to)));
+
+        assert_eq!(veclist.len(), 2);
+        assert_eq!(veclist.upper_bound(), 2);
+        assert_eq!(
+            veclist.iter_ordered().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec!['b', 'd']
+        );
+        assert_eq!(moves, vec![(1, 0), (3, 1)]);
+    }
+
+    #[test]
+    fn test_stale_key_rejected() {
+        let mut veclist = VecList::new();
+
+        let key = veclist.push(1);
+        assert_eq!(veclist.pop(key), Some(1));
+
+        // Slot is reused, but the old key must not alias the new value.
+        let new_key = veclist.push(2);
+        assert_eq!(new_key.index(), key.index());
+        assert_eq!(veclist.get(key), None);
+        assert_eq!(veclist.get(new_key), Some(&2));
+    }
+
+    #[test]
+    fn test_stale_key_rejected_by_insert_and_move() {
+        let mut veclist = VecList::new();
+
+        veclist.push_back('a');
+        let stale = veclist.push_back('b');
+        veclist.pop(stale);
+
+        // Slot is reused, but the stale key must not alias the new value.
+        let reused = veclist.push_back('c');
+        assert_eq!(reused.index(), stale.index());
+
+        assert_eq!(veclist.insert_before(stale, 'x'), None);
+        assert_eq!(veclist.insert_after(stale, 'x'), None);
+        assert!(!veclist.move_to_front(stale));
+        assert!(!veclist.move_to_back(stale));
+
+        // Nothing was spliced next to the reused value.
+        assert_eq!(
+            veclist.iter_ordered().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec!['a', 'c']
+        );
     }
 }